@@ -16,13 +16,104 @@ pub struct ID3v1 {
     comment: String,
     track: Option<u8>,
     genre: u8,
+    extension: ID3v1Extension,
+    // only set from a TAG+ (Enhanced) block
+    speed: Option<u8>,
+    genre_str: Option<String>,
 }
 
+// which (if any) of the non-standard extended id3v1 blocks was found adjacent to the tag
+#[derive(Debug, Clone, PartialEq)]
+pub enum ID3v1Extension {
+    None,
+    Enhanced,  // 227-byte `TAG+` block (id3v1.1 enhanced): 60-char title/artist/album, speed, genre string
+    Ext,       // 128-byte `EXT` block (id3v1.2)
+}
+
+// the standard 80-entry Winamp genre table (0-79) plus the common Winamp extensions (80-191)
+const GENRES: [&str; 192] = [
+    "Blues", "Classic Rock", "Country", "Dance", "Disco", "Funk", "Grunge", "Hip-Hop",
+    "Jazz", "Metal", "New Age", "Oldies", "Other", "Pop", "R&B", "Rap",
+    "Reggae", "Rock", "Techno", "Industrial", "Alternative", "Ska", "Death Metal", "Pranks",
+    "Soundtrack", "Euro-Techno", "Ambient", "Trip-Hop", "Vocal", "Jazz+Funk", "Fusion", "Trance",
+    "Classical", "Instrumental", "Acid", "House", "Game", "Sound Clip", "Gospel", "Noise",
+    "AlternRock", "Bass", "Soul", "Punk", "Space", "Meditative", "Instrumental Pop", "Instrumental Rock",
+    "Ethnic", "Gothic", "Darkwave", "Techno-Industrial", "Electronic", "Pop-Folk", "Eurodance", "Dream",
+    "Southern Rock", "Comedy", "Cult", "Gangsta", "Top 40", "Christian Rap", "Pop/Funk", "Jungle",
+    "Native American", "Cabaret", "New Wave", "Psychedelic", "Rave", "Showtunes", "Trailer", "Lo-Fi",
+    "Tribal", "Acid Punk", "Acid Jazz", "Polka", "Retro", "Musical", "Rock & Roll", "Hard Rock",
+    "Folk", "Folk-Rock", "National Folk", "Swing", "Fast Fusion", "Bebop", "Latin", "Revival",
+    "Celtic", "Bluegrass", "Avantgarde", "Gothic Rock", "Progressive Rock", "Psychedelic Rock", "Symphonic Rock", "Slow Rock",
+    "Big Band", "Chorus", "Easy Listening", "Acoustic", "Humour", "Speech", "Chanson", "Opera",
+    "Chamber Music", "Sonata", "Symphony", "Booty Bass", "Primus", "Porn Groove", "Satire", "Slow Jam",
+    "Club", "Tango", "Samba", "Folklore", "Ballad", "Power Ballad", "Rhythmic Soul", "Freestyle",
+    "Duet", "Punk Rock", "Drum Solo", "A Cappella", "Euro-House", "Dance Hall", "Goa", "Drum & Bass",
+    "Club-House", "Hardcore", "Terror", "Indie", "BritPop", "Negerpunk", "Polsk Punk", "Beat", "Christian Gangsta Rap",
+    "Heavy Metal", "Black Metal", "Crossover", "Contemporary Christian", "Christian Rock", "Merengue", "Salsa", "Thrash Metal",
+    "Anime", "JPop", "Synthpop", "Abstract", "Art Rock", "Baroque", "Bhangra", "Big Beat",
+    "Breakbeat", "Chillout", "Downtempo", "Dub", "EBM", "Eclectic", "Electro", "Electroclash",
+    "Emo", "Experimental", "Garage", "Global", "IDM", "Illbient", "Industro-Goth", "Jam Band",
+    "Krautrock", "Leftfield", "Lounge", "Math Rock", "New Romantic", "Nu-Breakz", "Post-Punk", "Post-Rock",
+    "Psytrance", "Shoegaze", "Space Rock", "Trop Rock", "World Music", "Neoclassical", "Audiobook", "Audio Theatre",
+    "Neue Deutsche Welle", "Podcast", "Indie Rock", "G-Funk", "Dubstep", "Garage Rock", "Psybient",
+];
+
 impl ID3v1 {
     // v1 tags have a fixed size
     pub const LEN_BYTES: usize = 128;
+    // the two non-standard extended blocks sit directly before the 128-byte tag
+    pub const ENHANCED_LEN_BYTES: usize = 227;  // TAG+ (id3v1.1 enhanced)
+    pub const EXT_LEN_BYTES: usize = 128;       // EXT (id3v1.2)
+
+    // resolves the raw genre byte against the Winamp genre table; 255 is the
+    // conventional "unset" sentinel, anything else out of range is unknown
+    pub fn genre_name(&self) -> &'static str {
+        match self.genre {
+            255 => "Unset",
+            g if (g as usize) < GENRES.len() => GENRES[g as usize],
+            _ => "Unknown",
+        }
+    }
 
-    pub fn create_from_binary(file_data: &[u8]) -> Result<ID3v1, TagError> {
+    pub fn set_title(&mut self, title: String) { self.title = title; }
+    pub fn set_artist(&mut self, artist: String) { self.artist = artist; }
+    pub fn set_album(&mut self, album: String) { self.album = album; }
+    pub fn set_year(&mut self, year: u64) { self.year = year; }
+    pub fn set_track(&mut self, track: Option<u8>) { self.track = track; }
+    pub fn set_genre(&mut self, genre: u8) { self.genre = genre; }
+
+    // which extended id3v1 block (if any) was found adjacent to this tag
+    pub fn extension(&self) -> &ID3v1Extension { &self.extension }
+
+    // speed and genre string, only present when a TAG+ (Enhanced) block was found
+    pub fn speed(&self) -> Option<u8> { self.speed }
+    pub fn genre_str(&self) -> Option<&str> { self.genre_str.as_deref() }
+
+    // inverse of create_from_binary: always writes the plain (non-extended) 128 byte layout
+    pub fn to_binary(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(ID3v1::LEN_BYTES);
+        buf.extend_from_slice(b"TAG");
+        buf.extend_from_slice(&pad_or_truncate(&self.title, 30));
+        buf.extend_from_slice(&pad_or_truncate(&self.artist, 30));
+        buf.extend_from_slice(&pad_or_truncate(&self.album, 30));
+        buf.extend_from_slice(&pad_or_truncate(&self.year.to_string(), 4));
+        match self.track {
+            Some(t) => {
+                buf.extend_from_slice(&pad_or_truncate(&self.comment, 28));
+                buf.push(0); // zero byte marking that a track number follows
+                buf.push(t);
+            }
+            None => {
+                buf.extend_from_slice(&pad_or_truncate(&self.comment, 30));
+            }
+        }
+        buf.push(self.genre);
+        buf
+    }
+
+    // `assume_utf8` is Config's opt-in for taggers that stuffed UTF-8 text into
+    // fields declared as Latin-1; see `decode_id3v1_field`.
+    pub fn create_from_binary(file_data: &[u8], assume_utf8: bool) -> Result<ID3v1, TagError> {
         // the tag is in the last 128 bytes starting with the string 'TAG'
         // 0..2 == 'TAG' (3 Bytes)
         // structure:
@@ -35,6 +126,9 @@ impl ID3v1 {
         // 126 == Track Number if =! 0 and previous byte = 0
         // 127 == Song Genre Identifier (integer matching list)
 
+        if file_data.len() < ID3v1::LEN_BYTES {
+            return Err(TagError::TagsNotFoundError);
+        }
         let start_of_tag = file_data.len() - ID3v1::LEN_BYTES;
 
         match &file_data[start_of_tag..start_of_tag + 3] {
@@ -42,41 +136,66 @@ impl ID3v1 {
                 // slice the relevant part
                 let id3_data = &file_data[start_of_tag..];
 
-                // extract: I think the conversions should be always valid
-                let title = unsafe_u8_to_str(&id3_data[3..33]).to_string();
-                let artist = unsafe_u8_to_str(&id3_data[33..63]).to_string();
-                let album = unsafe_u8_to_str(&id3_data[63..93]).to_string();
+                // extract: decode_id3v1_field never panics, even on non-UTF-8 data
+                let mut title = decode_id3v1_field(&id3_data[3..33], assume_utf8);
+                let mut artist = decode_id3v1_field(&id3_data[33..63], assume_utf8);
+                let mut album = decode_id3v1_field(&id3_data[63..93], assume_utf8);
 
                 // year is stored as string, transfer to int
-                let year_str = unsafe_u8_to_str(&id3_data[93..97]);
-                let year = match str::parse::<u64>(year_str) {
+                let year_str = decode_id3v1_field(&id3_data[93..97], assume_utf8);
+                let year = match str::parse::<u64>(&year_str) {
                     Ok(x) => x,
                     Err(_) => return Err(TagError::ParseError),
                 };
 
                 // logic for the optional track number depending on the zero byte
                 let mut track: Option<u8> = None;
-                let comment: String;
+                let mut comment: String;
                 match id3_data[125] {
                     0u8 => {  // byte is zero, check if year is set
                         match id3_data[126] {
                             0u8 => {  // no year --> comment
-                                comment = unsafe_u8_to_str(&id3_data[97..127]).to_string();
+                                comment = decode_id3v1_field(&id3_data[97..127], assume_utf8);
                             }
                             t => {  // year is set
                                 track = Some(t);
-                                comment = unsafe_u8_to_str(&id3_data[97..125]).to_string();
+                                comment = decode_id3v1_field(&id3_data[97..125], assume_utf8);
                             }
                         }
                     }
                     _ => {  // byte is non-zero --> long comment
-                            comment = unsafe_u8_to_str(&id3_data[97..127]).to_string();
+                            comment = decode_id3v1_field(&id3_data[97..127], assume_utf8);
                     }
                 }
                 let genre = &id3_data[127];
 
+                // check for an extended block sitting immediately before the 128-byte tag
+                let mut extension = ID3v1Extension::None;
+                let mut speed: Option<u8> = None;
+                let mut genre_str: Option<String> = None;
+                if start_of_tag >= ID3v1::ENHANCED_LEN_BYTES
+                    && &file_data[start_of_tag-ID3v1::ENHANCED_LEN_BYTES..start_of_tag-ID3v1::ENHANCED_LEN_BYTES+4] == b"TAG+" {
+                    // 227-byte TAG+ block: 60-char title/artist/album supersede the truncated
+                    // 30-char ones, plus a 1-byte speed and a 30-char genre string. Bounded to
+                    // this block (not to EOF) like the EXT block below.
+                    let enhanced = &file_data[start_of_tag-ID3v1::ENHANCED_LEN_BYTES..start_of_tag];
+                    title = decode_id3v1_field(&enhanced[4..64], assume_utf8);
+                    artist = decode_id3v1_field(&enhanced[64..124], assume_utf8);
+                    album = decode_id3v1_field(&enhanced[124..184], assume_utf8);
+                    speed = Some(enhanced[184]);
+                    genre_str = Some(decode_id3v1_field(&enhanced[185..215], assume_utf8));
+                    extension = ID3v1Extension::Enhanced;
+                } else if start_of_tag >= ID3v1::EXT_LEN_BYTES
+                    && &file_data[start_of_tag-ID3v1::EXT_LEN_BYTES..start_of_tag-ID3v1::EXT_LEN_BYTES+3] == b"EXT" {
+                    // 128-byte EXT block: the remainder holds an extended comment. Bounded to
+                    // this block so it doesn't swallow whatever sits after it (the adjacent TAG
+                    // tag itself).
+                    let ext = &file_data[start_of_tag-ID3v1::EXT_LEN_BYTES..start_of_tag];
+                    comment = decode_id3v1_field(&ext[3..], assume_utf8);
+                    extension = ID3v1Extension::Ext;
+                }
 
-                Ok(ID3v1 { title, artist, album, year, comment, track, genre: *genre})
+                Ok(ID3v1 { title, artist, album, year, comment, track, genre: *genre, extension, speed, genre_str})
             }
             _ => return Err(TagError::TagsNotFoundError)
         }
@@ -86,8 +205,8 @@ impl ID3v1 {
 impl fmt::Display for ID3v1 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self.track {
-            Some(t) => write!(f, "title: {}\nartist: {}\nalbum: {}\nyear: {}\ncomment: {}\ntrack: {}\ngenre: {}", self.title, self.artist,self.album,self.year,self.comment,t,self.genre),
-            None => write!(f, "title: {}\nartist: {}\nalbum: {}\nyear: {}\ncomment: {}\ntrack: {}\ngenre:", self.title, self.artist,self.album,self.year,self.comment,self.genre),
+            Some(t) => write!(f, "title: {}\nartist: {}\nalbum: {}\nyear: {}\ncomment: {}\ntrack: {}\ngenre: {}", self.title, self.artist,self.album,self.year,self.comment,t,self.genre_name()),
+            None => write!(f, "title: {}\nartist: {}\nalbum: {}\nyear: {}\ncomment: {}\ntrack: \ngenre: {}", self.title, self.artist,self.album,self.year,self.comment,self.genre_name()),
         }
     }
 
@@ -126,10 +245,19 @@ impl ID3v2 {
                 let extended_header = flags.bits[1];
                 let experimental_indicator = flags.bits[2];
                 let size = ID3v2::calculate_size(&header[6..10]);
-                let mut first_byte: usize = 10;
+
+                // reverse unsynchronization before walking frames, since it otherwise
+                // corrupts frame sizes wherever the encoder inserted a 0xFF 0x00 pair
+                let body = &file_data[10..10+size];
+                let buffer: Vec<u8> = match unsynchronization {
+                    true => ID3v2::reverse_unsynchronization(body),
+                    false => body.to_vec(),
+                };
+
+                let mut first_byte: usize = 0;
                 let mut frames = Vec::new();
-                while first_byte < size {
-                    let frame = ID3v2::parse_frame(&file_data, first_byte, *id3_version);
+                while first_byte < buffer.len() {
+                    let frame = ID3v2::parse_frame(&buffer, first_byte, *id3_version);
                     match frame {
                         Ok(x) => {
                             frames.push(x.0);
@@ -144,7 +272,11 @@ impl ID3v2 {
             _ => return Err(TagError::TagsNotFoundError)
         }
     }
-    fn calculate_size(bytes: &[u8]) -> usize {
+    // the parsed frames, for inspection or mutation via ID3v2Frame's setters
+    pub fn frames(&self) -> &[ID3v2Frame] { &self.frames }
+    pub fn frames_mut(&mut self) -> &mut Vec<ID3v2Frame> { &mut self.frames }
+
+    pub(crate) fn calculate_size(bytes: &[u8]) -> usize {
         // without the first 10 bytes
         // encoded as 4 bytes with 7 bits:
         // cast to u32, use only last 7 bits and shift accordingly
@@ -154,23 +286,99 @@ impl ID3v2 {
             + ((bytes[0] as u32 & 0x7F) << 21)
             ) as usize
     }
+
+    // undoes ID3v2 unsynchronization: wherever the encoder inserted a 0xFF 0x00 pair
+    // to avoid an accidental MPEG sync signal, drop the inserted 0x00
+    fn reverse_unsynchronization(data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::with_capacity(data.len());
+        let mut i = 0;
+        while i < data.len() {
+            out.push(data[i]);
+            if data[i] == 0xFF && i + 1 < data.len() && data[i+1] == 0x00 {
+                i += 2;  // drop the inserted 0x00
+            } else {
+                i += 1;
+            }
+        }
+        out
+    }
+
+    // inverse of calculate_size: splits a size into 4 bytes of 7 significant bits each
+    fn to_synchsafe(size: u32) -> [u8; 4] {
+        let mut bytes = [0u8; 4];
+        let mut remaining = size;
+        for i in (0..4).rev() {
+            bytes[i] = (remaining & 0x7F) as u8;
+            remaining >>= 7;
+        }
+        bytes
+    }
+
+    // inverse of create_from_binary: rebuilds the 10-byte header plus all frames.
+    // writing always clears the unsynchronization/extended-header/experimental flags,
+    // since the frames are serialized in their already-desynchronized form.
+    pub fn to_binary(&self) -> Vec<u8> {
+        let mut body = Vec::new();
+        for frame in &self.frames {
+            body.extend(frame.to_binary());
+        }
+        let size = body.len() as u32;
+
+        let mut out = Vec::with_capacity(10 + body.len());
+        out.extend_from_slice(b"ID3");
+        out.push(self.id3_version);
+        out.push(self.id3_revision);
+        out.push(0u8);
+        out.extend_from_slice(&ID3v2::to_synchsafe(size));
+        out.extend_from_slice(&body);
+        out
+    }
         fn parse_frame(file_data: &[u8], init: usize, version: u8) -> Result<(ID3v2Frame, usize), TagError> {
             let id: String;
             let size: u32;
             match version {
-                2 => return Err(TagError::ParseError),  // currently not supported
+                2 => {  // v2.2: 6-byte header, 3-char id, 3-byte big-endian size, no flags
+                    let header_len = 6;
+                    let id = unsafe_u8_to_str(&file_data[init..init+3]).to_string();
+                    let size_bytes = &file_data[init+3..init+6];
+                    let size = ((size_bytes[0] as usize) << 16)
+                        + ((size_bytes[1] as usize) << 8)
+                        + (size_bytes[2] as usize);
+                    let frame = ID3v2Frame::create_from_bytes(&file_data[init..(init+header_len+size)], version, id, size, Default::default());
+                    match frame {
+                        // last byte of this frame, so the caller can resume right after it
+                        Ok(x) => Ok((x, init+header_len+size-1)),
+                        Err(e) => Err(e)
+                    }
+                }
                 3 | 4 => {
+                    let header_len = 10;
                     let id = unsafe_u8_to_str(&file_data[init..init+4]).to_string();
-                    println!("id: {}", id);
                     let size_vec = file_data[init+4..init+8].to_vec();
                     let size = read_be_u32(&mut &size_vec[..]) as usize;
                     let flags = [
-                        BitArray::create_from_byte(file_data[init+9], true),
+                        BitArray::create_from_byte(file_data[init+8], true),
                         BitArray::create_from_byte(file_data[init+9], true),
                     ];
-                    let frame = ID3v2Frame::create_from_bytes(&file_data[init..(init+10+size)], version, id, size, flags);
+                    // v2.4 frame sizes are synchsafe, v2.3 ones a plain u32 (see ID3v2Frame::to_binary)
+                    let size = if version == 4 { ID3v2::calculate_size(&size_vec) } else { size };
+                    let raw = &file_data[init..(init+header_len+size)];
+                    // v2.4 also allows unsynchronization to be signaled per frame (the 'n' bit
+                    // of the format flags byte) instead of only at the tag-header level; the
+                    // header itself is untouched, only the frame body needs reversing
+                    let desynced;
+                    let frame_bytes: &[u8] = if version == 4 && flags[1].bits[6] {
+                        let mut v = raw[..header_len].to_vec();
+                        v.extend(ID3v2::reverse_unsynchronization(&raw[header_len..]));
+                        desynced = v;
+                        &desynced
+                    } else {
+                        raw
+                    };
+                    let frame = ID3v2Frame::create_from_bytes(frame_bytes, version, id, size, flags);
                     match frame {
-                        Ok(x) => Ok((x, init+size)),
+                        // last byte of this frame, so the caller can resume right after it
+                        Ok(x) => Ok((x, init+header_len+size-1)),
                         Err(e) => Err(e)
                     }
                 }
@@ -185,51 +393,159 @@ impl ID3v2 {
         id: String,
         size: usize,
         flags: [BitArray; 2],
-        data: String,
+        data: FrameContent,
+    }
+
+    // the decoded payload of a frame; which variant depends on the frame id
+    #[derive(Debug, Clone)]
+    pub enum FrameContent {
+        Text(String),
+        Comment { lang: [u8; 3], description: String, text: String },
+        Picture { mime: String, pic_type: u8, description: String, data: Vec<u8> },
+        Url(String),
+        Unknown(Vec<u8>),
+    }
+
+    impl Default for FrameContent {
+        fn default() -> Self { FrameContent::Unknown(Vec::new()) }
     }
 
 impl ID3v2Frame {
+    pub fn set_data(&mut self, data: FrameContent) { self.data = data; }
+
+    // inverse of create_from_bytes: re-encodes the frame body in UTF-8 (encoding byte 3)
+    pub fn to_binary(&self) -> Vec<u8> {
+        let body: Vec<u8> = match &self.data {
+            FrameContent::Text(s) => {
+                let mut b = vec![3u8]; // UTF-8 encoding byte
+                b.extend_from_slice(s.as_bytes());
+                b
+            }
+            FrameContent::Comment { lang, description, text } => {
+                let mut b = vec![3u8];
+                b.extend_from_slice(lang);
+                b.extend_from_slice(description.as_bytes());
+                b.push(0);
+                b.extend_from_slice(text.as_bytes());
+                b
+            }
+            FrameContent::Picture { mime, pic_type, description, data } => {
+                let mut b = vec![3u8];
+                b.extend_from_slice(mime.as_bytes());
+                b.push(0);
+                b.push(*pic_type);
+                b.extend_from_slice(description.as_bytes());
+                b.push(0);
+                b.extend_from_slice(data);
+                b
+            }
+            FrameContent::Url(s) if self.id == "WXXX" => {
+                // user-defined URL: ISO-8859-1 encoding byte and an empty description before the URL
+                let mut b = vec![0u8, 0u8];
+                b.extend_from_slice(s.as_bytes());
+                b
+            }
+            FrameContent::Url(s) => s.as_bytes().to_vec(),  // URL link frames carry no encoding byte
+            FrameContent::Unknown(bytes) => bytes.clone(),
+        };
+        let size = body.len() as u32;
+
+        let mut out = Vec::with_capacity(10 + body.len());
+        out.extend_from_slice(self.id.as_bytes());
+        match self.version {
+            2 => {  // v2.2: 3-byte big-endian size, no flag bytes
+                let size_bytes = size.to_be_bytes();
+                out.extend_from_slice(&size_bytes[1..]);
+            }
+            4 => {  // v2.4 frame sizes are synchsafe
+                out.extend_from_slice(&ID3v2::to_synchsafe(size));
+                out.push(self.flags[0].to_byte());
+                out.push(self.flags[1].to_byte());
+            }
+            _ => {  // v2.3 frame sizes are a plain u32
+                out.extend_from_slice(&size.to_be_bytes());
+                out.push(self.flags[0].to_byte());
+                out.push(self.flags[1].to_byte());
+            }
+        }
+        out.extend_from_slice(&body);
+        out
+    }
+
     pub fn create_from_bytes(bytes: &[u8], version: u8, id: String, size: usize, flags: [BitArray;2]) -> Result<ID3v2Frame, TagError> {
-        let data = match id.chars().next().unwrap() {
-            'T' => { // text field
-                let encoding = bytes[10];
-                ID3v2Frame::decode_text_frame(&bytes[11..], encoding)?
+        // v2.2 has a 6-byte frame header, v2.3/v2.4 have a 10-byte one
+        let header_len = match version {
+            2 => 6,
+            _ => 10,
+        };
+        let data = match id.as_str() {
+            "COMM" => { // comment: encoding, 3-byte lang, null-terminated description, then text
+                let encoding = bytes[header_len];
+                let lang = [bytes[header_len+1], bytes[header_len+2], bytes[header_len+3]];
+                let rest = &bytes[header_len+4..];
+                let (description_bytes, text_bytes) = split_null_terminated(rest, encoding);
+                let description = ID3v2Frame::decode_text_frame(description_bytes, encoding)?;
+                let text = ID3v2Frame::decode_text_frame(text_bytes, encoding)?;
+                FrameContent::Comment { lang, description, text }
             }
-            x => {
-                println!("{}",x);
-                return Err(TagError::ParseError)
+            "APIC" => { // picture: encoding, null-terminated mime, picture type, null-terminated description, raw image bytes
+                let encoding = bytes[header_len];
+                let rest = &bytes[header_len+1..];
+                let (mime_bytes, rest) = split_null_terminated(rest, 0);  // mime is always ISO-8859-1
+                let mime = mime_bytes.iter().map(|&b| b as char).collect::<String>();
+                let pic_type = rest[0];
+                let (description_bytes, picture_bytes) = split_null_terminated(&rest[1..], encoding);
+                let description = ID3v2Frame::decode_text_frame(description_bytes, encoding)?;
+                FrameContent::Picture { mime, pic_type, description, data: picture_bytes.to_vec() }
+            }
+            "WXXX" => { // user-defined URL: encoding, null-terminated description, then the URL (always ISO-8859-1)
+                let encoding = bytes[header_len];
+                let rest = &bytes[header_len+1..];
+                let (_description_bytes, url_bytes) = split_null_terminated(rest, encoding);
+                FrameContent::Url(url_bytes.iter().map(|&b| b as char).collect())
+            }
+            _ => match id.chars().next().unwrap() {
+                'T' => { // text field
+                    let encoding = bytes[header_len];
+                    FrameContent::Text(ID3v2Frame::decode_text_frame(&bytes[header_len+1..], encoding)?)
+                }
+                'W' => { // URL link frame: no encoding byte, always ISO-8859-1
+                    FrameContent::Url(bytes[header_len..].iter().map(|&b| b as char).collect())
+                }
+                _ => FrameContent::Unknown(bytes[header_len..].to_vec()),
             }
         };
-        //let data = match data_res {
-            //Ok(x) => x,
-            //Err(_) => return Err(TagError::ParseError),
-        //}
         Ok(ID3v2Frame { version, id: id, size, flags, data} )
     }
     fn decode_text_frame(text_bytes: &[u8], encoding: u8) -> Result<String, TagError> {
         match encoding {
-            0 => {  // ISO-8859-1
-                return Err(TagError::ParseError)
+            0 => {  // ISO-8859-1: each byte maps directly to the unicode scalar of the same value
+                let bytes = match text_bytes.last() {
+                    Some(0u8) => &text_bytes[..text_bytes.len()-1],  // strip trailing null terminator
+                    _ => text_bytes,
+                };
+                Ok(bytes.iter().map(|&b| b as char).collect::<String>())
             }
-            1 => {  // UTF-16
-                //let iter = (1..size)
-                    //.map(|i| u16::from_be_bytes(&[bytes[2*i],&bytes[2*i+1]));
-               ////for c in char::decode_utf16(bytes[1..]) {
-                    //data = decode_utf16(iter).collect::<String>().ok();
-                //let u16vec = BigEndian::read_u16(&bytes[1..]);
-                //for elem in u16vec[..] {
-                    //data.push(String::from_utf16(elem));
-                return Err(TagError::ParseError)
+            1 => {  // UTF-16 with a mandatory BOM
+                if text_bytes.len() < 2 {
+                    return Err(TagError::ParseError)
+                }
+                let big_endian = match &text_bytes[..2] {
+                    [0xFF, 0xFE] => false,
+                    [0xFE, 0xFF] => true,
+                    _ => return Err(TagError::ParseError),
+                };
+                decode_utf16_bytes(&text_bytes[2..], big_endian)
             }
-            2 => {  // UTF-16BE
-                //let data = String::new();
-                //let mut decoder = encoding_rs::UTF_16BE.new_decoder();
-                //decoder.decode_to_string(&text_bytes[11..], &mut data, true);
-                //println!("{}", data);
-                return Err(TagError::ParseError)
+            2 => {  // UTF-16BE, no BOM
+                decode_utf16_bytes(text_bytes, true)
             }
             3 => {  // UTF-8
-                match String::from_utf8(text_bytes.to_vec()) {
+                let bytes = match text_bytes.last() {
+                    Some(0u8) => &text_bytes[..text_bytes.len()-1],  // strip trailing null terminator
+                    _ => text_bytes,
+                };
+                match String::from_utf8(bytes.to_vec()) {
                     Ok(x) => Ok(x),
                     Err(e) => return Err(TagError::FromUtf8Error(e)),
                 }
@@ -240,9 +556,16 @@ impl ID3v2Frame {
             }
         }
     }
+    // lookup function that translates the frame ids to human readable strings,
+    // covering both the v2.2 three-char ids and their v2.3/v2.4 four-char equivalents
     pub fn id_to_fieldname(id: &str) -> String {
-        ///! lookup function that translates the frame ids to human readable strings
-        String::new()
+        match id {
+            "TT2" | "TIT2" => "title",
+            "TP1" | "TPE1" => "artist",
+            "TAL" | "TALB" => "album",
+            "TYE" | "TYER" => "year",
+            _ => "unknown",
+        }.to_string()
     }
 }
 
@@ -251,7 +574,18 @@ impl ID3v2Frame {
 
 impl fmt::Display for ID3v2 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "id3_version: {}\nid3_revision: {}\nunsynchronization: {}\nextended_header: {}\nexperimental_indicator: {}\nsize: {}\n",self.id3_version, self.id3_revision,self.unsynchronization,self.extended_header,self.experimental_indicator,self.size)
+        write!(f, "id3_version: {}\nid3_revision: {}\nunsynchronization: {}\nextended_header: {}\nexperimental_indicator: {}\nsize: {}\n",self.id3_version, self.id3_revision,self.unsynchronization,self.extended_header,self.experimental_indicator,self.size)?;
+        for frame in &self.frames {
+            let name = ID3v2Frame::id_to_fieldname(&frame.id);
+            match &frame.data {
+                FrameContent::Text(s) => writeln!(f, "{} ({}): {}", name, frame.id, s)?,
+                FrameContent::Comment { description, text, .. } => writeln!(f, "{} ({}): {} - {}", name, frame.id, description, text)?,
+                FrameContent::Picture { mime, description, data, .. } => writeln!(f, "{} ({}): {} ({}, {} bytes)", name, frame.id, description, mime, data.len())?,
+                FrameContent::Url(url) => writeln!(f, "{} ({}): {}", name, frame.id, url)?,
+                FrameContent::Unknown(data) => writeln!(f, "{} ({}): <{} bytes>", name, frame.id, data.len())?,
+            }
+        }
+        Ok(())
     }
 
 }
@@ -260,7 +594,7 @@ impl fmt::Display for ID3v2 {
 // helper structures and functions
 #[derive(Default)]
 pub struct BitArray {
-    bits: [bool; 8],
+    pub(crate) bits: [bool; 8],
     big_endian: bool,
 }
 
@@ -280,12 +614,91 @@ impl BitArray {
         BitArray { bits: tmp_arr, big_endian }
 
     }
+
+    // inverse of create_from_byte
+    pub fn to_byte(&self) -> u8 {
+        let mut byte = 0u8;
+        for i in 0..8 {
+            let bit = match self.big_endian {
+                true => self.bits[7-i],
+                false => self.bits[i],
+            };
+            if bit {
+                byte |= 1 << i;
+            }
+        }
+        byte
+    }
 }
 
 fn unsafe_u8_to_str(u8data: &[u8]) -> &str {
     str::from_utf8(&u8data).unwrap()
 }
 
+// decodes a fixed-width id3v1 text field without panicking, trimming trailing 0x00/space
+// padding. By default the field is treated as ISO-8859-1 (each byte maps directly to the
+// unicode scalar of the same value), which can never fail. When `assume_utf8` is set
+// (Config's opt-in for taggers that stuffed UTF-8 into declared-Latin-1 fields), valid
+// UTF-8 bytes are reinterpreted as UTF-8 instead.
+fn decode_id3v1_field(bytes: &[u8], assume_utf8: bool) -> String {
+    let trimmed = match bytes.iter().rposition(|&b| b != 0 && b != b' ') {
+        Some(i) => &bytes[..=i],
+        None => &bytes[..0],
+    };
+    if assume_utf8 {
+        if let Ok(s) = str::from_utf8(trimmed) {
+            return s.to_string();
+        }
+    }
+    trimmed.iter().map(|&b| b as char).collect()
+}
+
+// splits off a null-terminated field from `bytes`, returning (field, rest after terminator).
+// single-byte encodings (0, 3) terminate on one 0x00 byte; UTF-16 encodings (1, 2)
+// terminate on an aligned 0x0000 pair.
+fn split_null_terminated(bytes: &[u8], encoding: u8) -> (&[u8], &[u8]) {
+    match encoding {
+        1 | 2 => {
+            let mut i = 0;
+            while i + 1 < bytes.len() {
+                if bytes[i] == 0 && bytes[i+1] == 0 {
+                    return (&bytes[..i], &bytes[i+2..]);
+                }
+                i += 2;
+            }
+            (bytes, &[])
+        }
+        _ => match bytes.iter().position(|&b| b == 0) {
+            Some(i) => (&bytes[..i], &bytes[i+1..]),
+            None => (bytes, &[]),
+        }
+    }
+}
+
+// decodes a sequence of u16 code units (with a trailing 0x0000 terminator) into a String
+fn decode_utf16_bytes(bytes: &[u8], big_endian: bool) -> Result<String, TagError> {
+    let mut units: Vec<u16> = bytes.chunks_exact(2)
+        .map(|pair| match big_endian {
+            true => u16::from_be_bytes([pair[0], pair[1]]),
+            false => u16::from_le_bytes([pair[0], pair[1]]),
+        })
+        .collect();
+    if units.last() == Some(&0u16) {
+        units.pop();  // strip trailing null terminator
+    }
+    char::decode_utf16(units)
+        .collect::<Result<String, _>>()
+        .map_err(|_| TagError::ParseError)
+}
+
+// truncates (or zero-pads) the UTF-8 bytes of a string to exactly `len` bytes,
+// matching the fixed-width fields of the ID3v1 binary layout
+fn pad_or_truncate(s: &str, len: usize) -> Vec<u8> {
+    let mut bytes: Vec<u8> = s.bytes().take(len).collect();
+    bytes.resize(len, 0);
+    bytes
+}
+
 // from rust docs
 fn read_be_u32(input: &mut &[u8]) -> u32 {
     let (int_bytes, rest) = input.split_at(std::mem::size_of::<u32>());
@@ -301,7 +714,303 @@ mod tests {
     fn test_id3v2_size() {
         let bytes: [u8;4] = [1, 5, 7, 3]; // 0000001000010100001110000011
         let size = ID3v2::calculate_size(&bytes);
-        let expected: u32 = 2097152 + 81920 + 896 + 3;
+        let expected: usize = 2097152 + 81920 + 896 + 3;
         assert_eq!(size, expected);
     }
+
+    #[test]
+    fn test_id3v1_round_trip() {
+        let mut tag_bytes = vec![0u8; ID3v1::LEN_BYTES];
+        tag_bytes[0..3].copy_from_slice(b"TAG");
+        tag_bytes[3..8].copy_from_slice(b"Title");
+        tag_bytes[33..39].copy_from_slice(b"Artist");
+        tag_bytes[63..68].copy_from_slice(b"Album");
+        tag_bytes[93..97].copy_from_slice(b"2024");
+        tag_bytes[97..104].copy_from_slice(b"Comment");
+        tag_bytes[125] = 0;
+        tag_bytes[126] = 5; // track
+        tag_bytes[127] = 17; // genre: Rock
+
+        let mut tag = ID3v1::create_from_binary(&tag_bytes, false).unwrap();
+        assert_eq!(tag.genre_name(), "Rock");
+
+        tag.set_title("New Title".to_string());
+        let written = tag.to_binary();
+        let round_tripped = ID3v1::create_from_binary(&written, false).unwrap();
+
+        assert_eq!(round_tripped.title, "New Title");
+        assert_eq!(round_tripped.artist, "Artist");
+        assert_eq!(round_tripped.album, "Album");
+        assert_eq!(round_tripped.year, 2024);
+        assert_eq!(round_tripped.comment, "Comment");
+        assert_eq!(round_tripped.track, Some(5));
+        assert_eq!(round_tripped.genre_name(), "Rock");
+    }
+
+    #[test]
+    fn test_decode_text_frame_iso_8859_1() {
+        let bytes = [b'H', b'i', 0x00]; // null-terminated
+        let decoded = ID3v2Frame::decode_text_frame(&bytes, 0).unwrap();
+        assert_eq!(decoded, "Hi");
+    }
+
+    #[test]
+    fn test_decode_text_frame_utf16_with_bom() {
+        // little-endian BOM + "Hi" in UTF-16LE
+        let bytes = [0xFF, 0xFE, b'H', 0x00, b'i', 0x00];
+        let decoded = ID3v2Frame::decode_text_frame(&bytes, 1).unwrap();
+        assert_eq!(decoded, "Hi");
+    }
+
+    #[test]
+    fn test_decode_text_frame_utf16be() {
+        let bytes = [0x00, b'H', 0x00, b'i'];
+        let decoded = ID3v2Frame::decode_text_frame(&bytes, 2).unwrap();
+        assert_eq!(decoded, "Hi");
+    }
+
+    #[test]
+    fn test_create_from_bytes_comm() {
+        let mut bytes = vec![0u8; 10]; // dummy v2.3/v2.4 frame header, unused by create_from_bytes
+        bytes.push(0); // encoding: ISO-8859-1
+        bytes.extend_from_slice(b"eng");
+        bytes.extend_from_slice(b"desc\x00hello comment");
+        let body_len = bytes.len() - 10;
+
+        let frame = ID3v2Frame::create_from_bytes(&bytes, 3, "COMM".to_string(), body_len, Default::default()).unwrap();
+        match frame.data {
+            FrameContent::Comment { lang, description, text } => {
+                assert_eq!(lang, *b"eng");
+                assert_eq!(description, "desc");
+                assert_eq!(text, "hello comment");
+            }
+            _ => panic!("expected Comment"),
+        }
+    }
+
+    #[test]
+    fn test_create_from_bytes_apic() {
+        let mut bytes = vec![0u8; 10];
+        bytes.push(0); // encoding: ISO-8859-1
+        bytes.extend_from_slice(b"image/png\x00");
+        bytes.push(3); // pic_type: cover (front)
+        bytes.extend_from_slice(b"cover\x00");
+        bytes.extend_from_slice(&[0xFF, 0xD8, 0xFF]);
+        let body_len = bytes.len() - 10;
+
+        let frame = ID3v2Frame::create_from_bytes(&bytes, 3, "APIC".to_string(), body_len, Default::default()).unwrap();
+        match frame.data {
+            FrameContent::Picture { mime, pic_type, description, data } => {
+                assert_eq!(mime, "image/png");
+                assert_eq!(pic_type, 3);
+                assert_eq!(description, "cover");
+                assert_eq!(data, vec![0xFF, 0xD8, 0xFF]);
+            }
+            _ => panic!("expected Picture"),
+        }
+    }
+
+    #[test]
+    fn test_create_from_bytes_url() {
+        let mut bytes = vec![0u8; 10];
+        bytes.extend_from_slice(b"http://example.com");
+        let body_len = bytes.len() - 10;
+
+        let frame = ID3v2Frame::create_from_bytes(&bytes, 3, "WOAR".to_string(), body_len, Default::default()).unwrap();
+        match frame.data {
+            FrameContent::Url(url) => assert_eq!(url, "http://example.com"),
+            _ => panic!("expected Url"),
+        }
+    }
+
+    #[test]
+    fn test_create_from_bytes_wxxx() {
+        let mut bytes = vec![0u8; 10];
+        bytes.push(0); // encoding: ISO-8859-1
+        bytes.extend_from_slice(b"homepage\x00");
+        bytes.extend_from_slice(b"http://example.com");
+        let body_len = bytes.len() - 10;
+
+        let frame = ID3v2Frame::create_from_bytes(&bytes, 3, "WXXX".to_string(), body_len, Default::default()).unwrap();
+        match frame.data {
+            FrameContent::Url(url) => assert_eq!(url, "http://example.com"),
+            _ => panic!("expected Url"),
+        }
+    }
+
+    #[test]
+    fn test_id3v1_enhanced_tag_plus_block() {
+        let mut enhanced = vec![0u8; ID3v1::ENHANCED_LEN_BYTES];
+        enhanced[0..4].copy_from_slice(b"TAG+");
+        let long_title = b"A Very Long Title Exceeding Thirty Characters";
+        enhanced[4..4+long_title.len()].copy_from_slice(long_title);
+        enhanced[184] = 2; // speed
+        enhanced[185..185+4].copy_from_slice(b"Funk");
+
+        let mut core = vec![0u8; ID3v1::LEN_BYTES];
+        core[0..3].copy_from_slice(b"TAG");
+        core[93..97].copy_from_slice(b"2024");
+        core[127] = 5; // genre byte, should be superseded by nothing (Enhanced doesn't carry it)
+
+        let mut file_data = enhanced;
+        file_data.extend_from_slice(&core);
+
+        let tag = ID3v1::create_from_binary(&file_data, false).unwrap();
+        assert_eq!(*tag.extension(), ID3v1Extension::Enhanced);
+        assert_eq!(tag.title, "A Very Long Title Exceeding Thirty Characters");
+        assert_eq!(tag.speed(), Some(2));
+        assert_eq!(tag.genre_str(), Some("Funk"));
+    }
+
+    #[test]
+    fn test_id3v1_ext_block() {
+        let mut ext = vec![0u8; ID3v1::EXT_LEN_BYTES];
+        ext[0..3].copy_from_slice(b"EXT");
+        let msg = b"An extended comment";
+        ext[3..3+msg.len()].copy_from_slice(msg);
+
+        let mut core = vec![0u8; ID3v1::LEN_BYTES];
+        core[0..3].copy_from_slice(b"TAG");
+        core[93..97].copy_from_slice(b"2024");
+
+        let mut file_data = ext;
+        file_data.extend_from_slice(&core);
+
+        let tag = ID3v1::create_from_binary(&file_data, false).unwrap();
+        assert_eq!(*tag.extension(), ID3v1Extension::Ext);
+        assert_eq!(tag.comment, "An extended comment");
+        // the bug this guards against: the comment must not swallow the adjacent TAG block
+        assert!(!tag.comment.contains("TAG"));
+    }
+
+    // builds a minimal single-frame ID3v2 tag: a TIT2 text frame containing `text`,
+    // using a plain u32 frame size for v2.3 and a synchsafe one for v2.4
+    fn build_id3v2_tag(version: u8, text: &str) -> Vec<u8> {
+        let mut body = vec![3u8]; // UTF-8 encoding byte
+        body.extend_from_slice(text.as_bytes());
+        let body_size = body.len() as u32;
+
+        let mut frame = Vec::new();
+        frame.extend_from_slice(b"TIT2");
+        if version == 4 {
+            frame.extend_from_slice(&ID3v2::to_synchsafe(body_size));
+        } else {
+            frame.extend_from_slice(&body_size.to_be_bytes());
+        }
+        frame.push(0); // status flags
+        frame.push(0); // format flags
+        frame.extend_from_slice(&body);
+
+        let mut tag = Vec::new();
+        tag.extend_from_slice(b"ID3");
+        tag.push(version);
+        tag.push(0); // revision
+        tag.push(0); // flags
+        tag.extend_from_slice(&ID3v2::to_synchsafe(frame.len() as u32));
+        tag.extend_from_slice(&frame);
+        tag
+    }
+
+    #[test]
+    fn test_id3v2_round_trip_v3() {
+        let file_data = build_id3v2_tag(3, "Hello");
+        let mut tag = ID3v2::create_from_binary(&file_data).unwrap();
+        assert_eq!(tag.frames().len(), 1);
+        match &tag.frames()[0].data {
+            FrameContent::Text(s) => assert_eq!(s, "Hello"),
+            _ => panic!("expected Text"),
+        }
+
+        tag.frames_mut()[0].set_data(FrameContent::Text("New Title".to_string()));
+        let written = tag.to_binary();
+        let reparsed = ID3v2::create_from_binary(&written).unwrap();
+        assert_eq!(reparsed.frames().len(), 1);
+        match &reparsed.frames()[0].data {
+            FrameContent::Text(s) => assert_eq!(s, "New Title"),
+            _ => panic!("expected Text"),
+        }
+    }
+
+    #[test]
+    fn test_id3v2_round_trip_v4() {
+        let file_data = build_id3v2_tag(4, "Hello");
+        let mut tag = ID3v2::create_from_binary(&file_data).unwrap();
+        assert_eq!(tag.frames().len(), 1);
+        match &tag.frames()[0].data {
+            FrameContent::Text(s) => assert_eq!(s, "Hello"),
+            _ => panic!("expected Text"),
+        }
+
+        tag.frames_mut()[0].set_data(FrameContent::Text("New Title".to_string()));
+        let written = tag.to_binary();
+        let reparsed = ID3v2::create_from_binary(&written).unwrap();
+        assert_eq!(reparsed.frames().len(), 1);
+        match &reparsed.frames()[0].data {
+            FrameContent::Text(s) => assert_eq!(s, "New Title"),
+            _ => panic!("expected Text"),
+        }
+    }
+
+    #[test]
+    fn test_parse_frame_v2_2_three_char_id() {
+        // v2.2: 3-char id, 6-byte header, body is an encoding byte + UTF-8 text
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"TT2");
+        let body = [3u8, b'H', b'i']; // UTF-8 encoding byte + "Hi"
+        bytes.extend_from_slice(&(body.len() as u32).to_be_bytes()[1..]); // 3-byte BE size
+        bytes.extend_from_slice(&body);
+
+        let (frame, end) = ID3v2::parse_frame(&bytes, 0, 2).unwrap();
+        assert_eq!(frame.id, "TT2");
+        assert_eq!(end, bytes.len() - 1); // last byte of the frame
+        match frame.data {
+            FrameContent::Text(s) => assert_eq!(s, "Hi"),
+            _ => panic!("expected Text"),
+        }
+        assert_eq!(ID3v2Frame::id_to_fieldname(&frame.id), "title");
+    }
+
+    #[test]
+    fn test_create_from_binary_reverses_tag_level_unsynchronization() {
+        // TIT2 frame body, ISO-8859-1 encoded "A\u{FF}Z", escaped for unsynchronization:
+        // the 0xFF byte has a 0x00 inserted right after it on disk
+        let escaped_body = [0u8, b'A', 0xFF, 0x00, b'Z'];
+        let mut frame = Vec::new();
+        frame.extend_from_slice(b"TIT2");
+        frame.extend_from_slice(&4u32.to_be_bytes()); // logical (post-reversal) body size
+        frame.extend_from_slice(&[0, 0]); // frame flags
+        frame.extend_from_slice(&escaped_body);
+
+        let mut file_data = Vec::new();
+        file_data.extend_from_slice(b"ID3");
+        file_data.extend_from_slice(&[3, 0]); // version, revision
+        file_data.push(0x80); // tag-level unsynchronization flag set
+        file_data.extend_from_slice(&ID3v2::to_synchsafe(frame.len() as u32));
+        file_data.extend_from_slice(&frame);
+
+        let tag = ID3v2::create_from_binary(&file_data).unwrap();
+        assert_eq!(tag.frames().len(), 1);
+        match &tag.frames()[0].data {
+            FrameContent::Text(s) => assert_eq!(s, "A\u{FF}Z"),
+            _ => panic!("expected Text"),
+        }
+    }
+
+    #[test]
+    fn test_parse_frame_honors_per_frame_v2_4_unsync_flag() {
+        // same escaped body as above, but unsynchronization is signaled only on this frame
+        // (the 'n' bit, 0x02, of the format flags byte), with the tag-level flag clear
+        let escaped_body = [0u8, b'A', 0xFF, 0x00, b'Z'];
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"TIT2");
+        bytes.extend_from_slice(&(escaped_body.len() as u32).to_be_bytes()); // on-disk body size
+        bytes.extend_from_slice(&[0, 0x02]); // status flags, format flags with 'n' set
+        bytes.extend_from_slice(&escaped_body);
+
+        let (frame, _end) = ID3v2::parse_frame(&bytes, 0, 4).unwrap();
+        match frame.data {
+            FrameContent::Text(s) => assert_eq!(s, "A\u{FF}Z"),
+            _ => panic!("expected Text"),
+        }
+    }
 }
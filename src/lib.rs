@@ -3,6 +3,7 @@ use std::fmt;
 use std::io;
 use std::io::Read;
 use std::io::BufReader;
+use std::fs;
 use std::fs::File;
 use std::str;
 
@@ -33,7 +34,7 @@ pub fn run(config: &Config) -> Result<(), Box<dyn Error>> {
     let binary_data = read_bytewise_from_file(&config.filename)?;
     let mut f_tags = FileTags::new();
 
-    match extract_id3(&binary_data, &mut f_tags) {
+    match extract_id3(&binary_data, &mut f_tags, config) {
         Ok(_) => (),
         Err(TagError::ParseError) => panic!("Tag found but could not be parsed"),
         Err(e) => panic!("Some other error: {:?}",e),  // supress other warnings for the moment
@@ -54,8 +55,8 @@ pub fn read_bytewise_from_file(filename: &str) -> Result<Vec<u8>, io::Error> {
     Ok(buffer)
 }
 
-pub fn extract_id3(data: &[u8], f_tags: &mut FileTags) -> Result<(), TagError> {
-    let mut id3v1_tags = id3::ID3v1::create_from_binary(&data);
+pub fn extract_id3(data: &[u8], f_tags: &mut FileTags, config: &Config) -> Result<(), TagError> {
+    let mut id3v1_tags = id3::ID3v1::create_from_binary(&data, config.assume_utf8);
     let mut id3v2_tags = id3::ID3v2::create_from_binary(&data);
     match id3v1_tags {
         Ok(x) => {f_tags.id3v1 = Some(x);} ,
@@ -71,8 +72,51 @@ pub fn extract_id3(data: &[u8], f_tags: &mut FileTags) -> Result<(), TagError> {
 }
 
 
+// rewrites the id3v2 block at the start and the id3v1 trailer at the end of `filename`,
+// taking whatever values are currently set on `tags`
+pub fn write_tags(filename: &str, tags: &FileTags) -> Result<(), TagError> {
+    let mut data = read_bytewise_from_file(filename).map_err(TagError::IoError)?;
+
+    if let Some(id3v2) = &tags.id3v2 {
+        let old_len = if data.len() >= 10 && &data[..3] == b"ID3" {
+            10 + id3::ID3v2::calculate_size(&data[6..10])
+        } else {
+            0
+        };
+        data.splice(..old_len, id3v2.to_binary());
+    }
+
+    if let Some(id3v1) = &tags.id3v1 {
+        let new_trailer = id3v1.to_binary();
+        let len = data.len();
+        // to_binary() always writes the plain 128-byte layout, never an extension block, so if
+        // an existing tag has an adjacent TAG+/EXT block we strip that too -- otherwise it would
+        // be left on disk holding stale fields that contradict the freshly written tag
+        let splice_start = if len >= id3::ID3v1::LEN_BYTES && &data[len - id3::ID3v1::LEN_BYTES..len - id3::ID3v1::LEN_BYTES + 3] == b"TAG" {
+            let start_of_tag = len - id3::ID3v1::LEN_BYTES;
+            if start_of_tag >= id3::ID3v1::ENHANCED_LEN_BYTES
+                && &data[start_of_tag-id3::ID3v1::ENHANCED_LEN_BYTES..start_of_tag-id3::ID3v1::ENHANCED_LEN_BYTES+4] == b"TAG+" {
+                start_of_tag - id3::ID3v1::ENHANCED_LEN_BYTES
+            } else if start_of_tag >= id3::ID3v1::EXT_LEN_BYTES
+                && &data[start_of_tag-id3::ID3v1::EXT_LEN_BYTES..start_of_tag-id3::ID3v1::EXT_LEN_BYTES+3] == b"EXT" {
+                start_of_tag - id3::ID3v1::EXT_LEN_BYTES
+            } else {
+                start_of_tag
+            }
+        } else {
+            len
+        };
+        data.splice(splice_start.., new_trailer);
+    }
+
+    fs::write(filename, data).map_err(TagError::IoError)
+}
+
 pub struct Config {
     pub filename: String,
+    // opt-in: reinterpret a Latin-1-declared id3v1 field as UTF-8 when it is valid UTF-8,
+    // for taggers that stuffed UTF-8 into those fields
+    pub assume_utf8: bool,
 }
 
 
@@ -82,7 +126,8 @@ impl Config {
             return Err("Not enough arguments specified");
         }
         let filename = args[1].clone();
-        Ok(Config { filename })
+        let assume_utf8 = args.iter().any(|arg| arg == "--assume-utf8");
+        Ok(Config { filename, assume_utf8 })
     }
 }
 
@@ -99,6 +144,11 @@ impl FileTags {
             id3v2: None,
         }
     }
+    pub fn id3v1(&self) -> Option<&id3::ID3v1> { self.id3v1.as_ref() }
+    pub fn id3v1_mut(&mut self) -> Option<&mut id3::ID3v1> { self.id3v1.as_mut() }
+    pub fn id3v2(&self) -> Option<&id3::ID3v2> { self.id3v2.as_ref() }
+    pub fn id3v2_mut(&mut self) -> Option<&mut id3::ID3v2> { self.id3v2.as_mut() }
+
     pub fn print_tags(self) {
         match self.id3v1 {
             Some(x) => println!("\nid3v1 found:\n{}", x),
@@ -115,6 +165,7 @@ impl FileTags {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use id3::BitArray;
 
     #[test]
     fn test_read() {